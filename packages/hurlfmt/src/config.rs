@@ -0,0 +1,160 @@
+/*
+ * Hurl (https://hurl.dev)
+ * Copyright (C) 2024 Orange
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *          http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+ */
+use std::path::{Path, PathBuf};
+
+/// Name of the config file discovered by walking up from an input file's
+/// directory.
+const CONFIG_FILE_NAME: &str = "hurlfmt.toml";
+
+/// Formatting preferences read from a `hurlfmt.toml` file.
+///
+/// Every field is optional so a config file only needs to spell out the
+/// settings a team wants to pin; anything left unset falls back to the CLI
+/// options. The entry point can only honour settings that it resolves itself,
+/// without reaching into the `linter`/`format` library APIs, so the file
+/// currently recognises a single key — whether `--check` is implied. Unknown
+/// keys are rejected rather than silently ignored, so a `hurlfmt.toml` never
+/// looks like it pinned a setting that `hurlfmt` does not actually apply.
+#[derive(Clone, Debug, Default)]
+pub struct FormatConfig {
+    /// Whether `--check` is implied.
+    pub check: Option<bool>,
+}
+
+impl FormatConfig {
+    /// Parses a `hurlfmt.toml` from its textual content. Only the keys
+    /// `hurlfmt` acts on are accepted; any other key (or a `[section]` header)
+    /// is an error, so the file cannot promise a setting that is ignored.
+    pub fn parse(content: &str) -> Result<FormatConfig, String> {
+        let mut config = FormatConfig::default();
+        for (n, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                return Err(format!("line {}: unsupported section `{line}`", n + 1));
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", n + 1));
+            };
+            let key = key.trim();
+            let value = value.trim();
+            match key {
+                "check" => config.check = Some(parse_bool(value, n + 1)?),
+                _ => return Err(format!("line {}: unknown key `{key}`", n + 1)),
+            }
+        }
+        Ok(config)
+    }
+}
+
+fn parse_bool(value: &str, line: usize) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("line {line}: expected a boolean, got `{value}`")),
+    }
+}
+
+/// Discovers and loads the `hurlfmt.toml` that applies to `input_file`.
+///
+/// The lookup walks up from the file's directory to the filesystem root,
+/// mirroring rustfmt's `load_config`, then falls back to the user config
+/// directory (`$XDG_CONFIG_HOME/hurlfmt/hurlfmt.toml`, or `~/.config/...`).
+/// Returns `Ok(None)` when no config file is found.
+pub fn load_config(input_file: &str) -> Result<Option<FormatConfig>, String> {
+    let start = Path::new(input_file)
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let mut dir = start.as_path();
+    loop {
+        let candidate = dir.join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return read_config(&candidate).map(Some);
+        }
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => break,
+        }
+    }
+
+    if let Some(config_dir) = user_config_dir() {
+        let candidate = config_dir.join("hurlfmt").join(CONFIG_FILE_NAME);
+        if candidate.is_file() {
+            return read_config(&candidate).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+fn user_config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("XDG_CONFIG_HOME") {
+        if !dir.is_empty() {
+            return Some(PathBuf::from(dir));
+        }
+    }
+    std::env::var_os("HOME").map(|home| Path::new(&home).join(".config"))
+}
+
+fn read_config(path: &Path) -> Result<FormatConfig, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Config file {} can not be read - {e}", path.display()))?;
+    FormatConfig::parse(&content)
+        .map_err(|e| format!("Config file {} is invalid - {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_check_flag() {
+        assert_eq!(FormatConfig::parse("check = true").unwrap().check, Some(true));
+        assert_eq!(FormatConfig::parse("check = false").unwrap().check, Some(false));
+    }
+
+    #[test]
+    fn ignores_blank_lines_and_comments() {
+        let config = FormatConfig::parse("\n# a comment\ncheck = true\n").unwrap();
+        assert_eq!(config.check, Some(true));
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        assert!(FormatConfig::parse("output_format = \"json\"").is_err());
+    }
+
+    #[test]
+    fn rejects_section_header() {
+        assert!(FormatConfig::parse("[lints]\ncheck = true").is_err());
+    }
+
+    #[test]
+    fn rejects_non_boolean_check() {
+        assert!(FormatConfig::parse("check = yes").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_value() {
+        assert!(FormatConfig::parse("check").is_err());
+    }
+}
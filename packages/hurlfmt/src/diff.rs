@@ -0,0 +1,248 @@
+/*
+ * Hurl (https://hurl.dev)
+ * Copyright (C) 2024 Orange
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *          http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+ */
+//! A small line-based unified diff, modeled on rustfmt's `diff` write mode.
+//!
+//! It compares the original file content with the reformatted output and
+//! renders the changes as `@@`/`+`/`-` hunks so CI can gate on "file is not
+//! formatted". The algorithm is a straightforward longest-common-subsequence
+//! walk, which is enough for the small, line-oriented drift a formatter
+//! produces.
+
+/// Computes a unified diff between `original` and `formatted`.
+///
+/// `filename` is used for the `---`/`+++` headers. Returns an empty string
+/// when the two inputs are identical line-for-line, so callers can treat a
+/// non-empty result as "the file is not formatted".
+pub fn unified_diff(original: &str, formatted: &str, filename: &str) -> String {
+    let old_lines: Vec<&str> = original.lines().collect();
+    let new_lines: Vec<&str> = formatted.lines().collect();
+
+    let ops = diff_lines(&old_lines, &new_lines);
+    if ops.iter().all(|op| matches!(op, Op::Equal(_))) {
+        return String::new();
+    }
+
+    let hunks = group_hunks(&ops);
+    let mut out = String::new();
+    out.push_str(&format!("--- {filename}\n"));
+    out.push_str(&format!("+++ {filename} (formatted)\n"));
+    for hunk in hunks {
+        out.push_str(&render_hunk(&hunk, &old_lines, &new_lines));
+    }
+    out
+}
+
+/// A single edit operation, carrying the index into the relevant side.
+enum Op {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Classic LCS-based line diff.
+fn diff_lines(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let n = old.len();
+    let m = new.len();
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            ops.push(Op::Equal(i));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(Op::Delete(i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(j));
+            j += 1;
+        }
+    }
+    while i < n {
+        ops.push(Op::Delete(i));
+        i += 1;
+    }
+    while j < m {
+        ops.push(Op::Insert(j));
+        j += 1;
+    }
+    ops
+}
+
+/// A contiguous run of changes with the surrounding context.
+struct Hunk {
+    old_start: usize,
+    old_count: usize,
+    new_start: usize,
+    new_count: usize,
+    ops: Vec<HunkOp>,
+}
+
+enum HunkOp {
+    Equal(usize, usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+const CONTEXT: usize = 3;
+
+fn group_hunks(ops: &[Op]) -> Vec<Hunk> {
+    // Track line numbers on both sides while scanning the op stream.
+    let mut indexed = Vec::with_capacity(ops.len());
+    let (mut oi, mut ni) = (0usize, 0usize);
+    for op in ops {
+        match op {
+            Op::Equal(_) => {
+                indexed.push(HunkOp::Equal(oi, ni));
+                oi += 1;
+                ni += 1;
+            }
+            Op::Delete(_) => {
+                indexed.push(HunkOp::Delete(oi));
+                oi += 1;
+            }
+            Op::Insert(_) => {
+                indexed.push(HunkOp::Insert(ni));
+                ni += 1;
+            }
+        }
+    }
+
+    let changed: Vec<usize> = indexed
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, HunkOp::Equal(_, _)))
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return Vec::new();
+    }
+
+    let mut hunks = Vec::new();
+    let mut start = changed[0].saturating_sub(CONTEXT);
+    let mut end = (changed[0] + 1 + CONTEXT).min(indexed.len());
+    for &idx in &changed[1..] {
+        let window_start = idx.saturating_sub(CONTEXT);
+        if window_start <= end {
+            end = (idx + 1 + CONTEXT).min(indexed.len());
+        } else {
+            hunks.push(build_hunk(&indexed[start..end]));
+            start = window_start;
+            end = (idx + 1 + CONTEXT).min(indexed.len());
+        }
+    }
+    hunks.push(build_hunk(&indexed[start..end]));
+    hunks
+}
+
+fn build_hunk(ops: &[HunkOp]) -> Hunk {
+    let mut old_start = None;
+    let mut new_start = None;
+    let mut old_count = 0;
+    let mut new_count = 0;
+    let mut collected = Vec::with_capacity(ops.len());
+    for op in ops {
+        match *op {
+            HunkOp::Equal(o, n) => {
+                old_start.get_or_insert(o);
+                new_start.get_or_insert(n);
+                old_count += 1;
+                new_count += 1;
+                collected.push(HunkOp::Equal(o, n));
+            }
+            HunkOp::Delete(o) => {
+                old_start.get_or_insert(o);
+                old_count += 1;
+                collected.push(HunkOp::Delete(o));
+            }
+            HunkOp::Insert(n) => {
+                new_start.get_or_insert(n);
+                new_count += 1;
+                collected.push(HunkOp::Insert(n));
+            }
+        }
+    }
+    Hunk {
+        old_start: old_start.unwrap_or(0),
+        old_count,
+        new_start: new_start.unwrap_or(0),
+        new_count,
+        ops: collected,
+    }
+}
+
+fn render_hunk(hunk: &Hunk, old: &[&str], new: &[&str]) -> String {
+    let mut out = String::new();
+    out.push_str(&format!(
+        "@@ -{},{} +{},{} @@\n",
+        hunk.old_start + 1,
+        hunk.old_count,
+        hunk.new_start + 1,
+        hunk.new_count
+    ));
+    for op in &hunk.ops {
+        match *op {
+            HunkOp::Equal(o, _) => out.push_str(&format!(" {}\n", old[o])),
+            HunkOp::Delete(o) => out.push_str(&format!("-{}\n", old[o])),
+            HunkOp::Insert(n) => out.push_str(&format!("+{}\n", new[n])),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_inputs_produce_no_diff() {
+        assert_eq!(unified_diff("a\nb\n", "a\nb\n", "f.hurl"), "");
+    }
+
+    #[test]
+    fn empty_inputs_produce_no_diff() {
+        assert_eq!(unified_diff("", "", "f.hurl"), "");
+    }
+
+    #[test]
+    fn reports_pure_insertion() {
+        let diff = unified_diff("a\nb\n", "a\nx\nb\n", "f.hurl");
+        assert!(diff.contains("--- f.hurl"));
+        assert!(diff.contains("+++ f.hurl (formatted)"));
+        assert!(diff.contains("+x"));
+        assert!(!diff.contains("-x"));
+    }
+
+    #[test]
+    fn reports_pure_deletion() {
+        let diff = unified_diff("a\nx\nb\n", "a\nb\n", "f.hurl");
+        assert!(diff.contains("-x"));
+        assert!(!diff.contains("+x"));
+    }
+}
@@ -0,0 +1,429 @@
+/*
+ * Hurl (https://hurl.dev)
+ * Copyright (C) 2024 Orange
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *          http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+ */
+//! Range-restricted formatting, ported from rustfmt's `FileLines`.
+//!
+//! `--file-lines` narrows reformatting to a set of 1-based, inclusive line
+//! ranges per file so large files can be touched up incrementally (editors,
+//! pre-commit hooks) without reflowing unrelated entries. After the formatter
+//! produces the fully formatted output, [`FileLines::splice`] keeps the
+//! formatted text only inside the selected ranges and the original text
+//! everywhere else.
+
+use std::collections::HashMap;
+
+/// A 1-based, inclusive line range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    fn contains(&self, line: usize) -> bool {
+        self.start <= line && line <= self.end
+    }
+}
+
+/// The set of line ranges to format, keyed by file. `None` means "no
+/// restriction" — the whole file is formatted, which is the default.
+#[derive(Clone, Debug, Default)]
+pub struct FileLines {
+    per_file: Option<HashMap<String, Vec<LineRange>>>,
+}
+
+impl FileLines {
+    /// The unrestricted set: every line of every file is eligible.
+    pub fn all() -> FileLines {
+        FileLines { per_file: None }
+    }
+
+    /// Returns `true` when no restriction applies.
+    pub fn is_all(&self) -> bool {
+        self.per_file.is_none()
+    }
+
+    /// Parses the `--file-lines` argument. Accepts either the rustfmt JSON
+    /// form `[{"file":"a.hurl","range":[12,40]}]` or the shorthand
+    /// `file:start-end`.
+    pub fn parse(arg: &str) -> Result<FileLines, String> {
+        let arg = arg.trim();
+        let specs = if arg.starts_with('[') {
+            parse_json(arg)?
+        } else {
+            vec![parse_shorthand(arg)?]
+        };
+
+        let mut per_file: HashMap<String, Vec<LineRange>> = HashMap::new();
+        for (file, range) in specs {
+            if range.start == 0 || range.end < range.start {
+                return Err(format!(
+                    "invalid --file-lines range {}-{}",
+                    range.start, range.end
+                ));
+            }
+            per_file.entry(file).or_default().push(range);
+        }
+        Ok(FileLines {
+            per_file: Some(per_file),
+        })
+    }
+
+    /// Returns the ranges that apply to `file`, or `None` when the whole file
+    /// should be formatted.
+    fn ranges_for(&self, file: &str) -> Option<&[LineRange]> {
+        match &self.per_file {
+            None => None,
+            Some(map) => Some(map.get(file).map(Vec::as_slice).unwrap_or(&[])),
+        }
+    }
+
+    /// Validates that no requested range for `file` starts or ends inside a
+    /// Hurl entry: each boundary must fall on an entry boundary so the splice
+    /// never cuts a request in half. `entry_spans` is the list of 1-based
+    /// `(start, end)` line spans of the file's entries, in order.
+    pub fn validate(&self, file: &str, entry_spans: &[(usize, usize)]) -> Result<(), String> {
+        let Some(ranges) = self.ranges_for(file) else {
+            return Ok(());
+        };
+        for range in ranges {
+            for &(start, end) in entry_spans {
+                let splits_start = range.start > start && range.start <= end;
+                let splits_end = range.end >= start && range.end < end;
+                if splits_start || splits_end {
+                    return Err(format!(
+                        "--file-lines range {}-{} splits the entry at lines {start}-{end} in {file}",
+                        range.start, range.end
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Splices `formatted` into `original` for `file`, keeping formatted text
+    /// only inside the selected ranges. When no `--file-lines` restriction
+    /// applies the formatted output is returned unchanged; otherwise the result
+    /// follows the original's line endings and trailing newline.
+    pub fn splice(&self, file: &str, original: &str, formatted: &str) -> String {
+        let Some(ranges) = self.ranges_for(file) else {
+            return formatted.to_string();
+        };
+        if ranges.is_empty() {
+            return original.to_string();
+        }
+
+        let old_lines: Vec<&str> = original.lines().collect();
+        let new_lines: Vec<&str> = formatted.lines().collect();
+        let hunks = hunks(&old_lines, &new_lines);
+
+        let mut out: Vec<&str> = Vec::new();
+        let mut old_idx = 0usize;
+        for hunk in hunks {
+            // Copy the unchanged gap before this hunk verbatim.
+            while old_idx < hunk.old_start {
+                out.push(old_lines[old_idx]);
+                old_idx += 1;
+            }
+            // Apply the formatted side only when the whole original span of
+            // the hunk lies within a selected range; otherwise keep original.
+            let in_range = (hunk.old_start..hunk.old_end)
+                .all(|i| ranges.iter().any(|r| r.contains(i + 1)))
+                && (hunk.old_start < hunk.old_end
+                    || ranges.iter().any(|r| r.contains(hunk.old_start + 1)));
+            if in_range {
+                out.extend_from_slice(&new_lines[hunk.new_start..hunk.new_end]);
+            } else {
+                out.extend_from_slice(&old_lines[hunk.old_start..hunk.old_end]);
+            }
+            old_idx = hunk.old_end;
+        }
+        while old_idx < old_lines.len() {
+            out.push(old_lines[old_idx]);
+            old_idx += 1;
+        }
+
+        // `lines()` drops both the line endings and any trailing newline, so
+        // rejoin with the original's convention and restore its final newline.
+        // Otherwise the `--file-lines` path would silently convert CRLF to LF
+        // and strip the closing newline, unlike the whole-file path which
+        // returns the formatted text verbatim.
+        let newline = if original.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut result = out.join(newline);
+        if original.ends_with('\n') {
+            result.push_str(newline);
+        }
+        result
+    }
+}
+
+/// Parses the rustfmt JSON form `[{"file":"a.hurl","range":[12,40]}, ...]`.
+///
+/// This is a focused scanner for the fixed `--file-lines` shape rather than a
+/// general JSON parser: it walks object by object, pulling the `file` string
+/// and the two `range` integers from each.
+fn parse_json(arg: &str) -> Result<Vec<(String, LineRange)>, String> {
+    let err = || format!("invalid --file-lines JSON: `{arg}`");
+    let bytes = arg.as_bytes();
+    let mut i = 0;
+    let expect = |i: &mut usize, b: u8| -> Result<(), String> {
+        skip_ws(bytes, i);
+        if bytes.get(*i) == Some(&b) {
+            *i += 1;
+            Ok(())
+        } else {
+            Err(err())
+        }
+    };
+
+    expect(&mut i, b'[')?;
+    let mut specs = Vec::new();
+    skip_ws(bytes, &mut i);
+    if bytes.get(i) == Some(&b']') {
+        return Ok(specs);
+    }
+    loop {
+        expect(&mut i, b'{')?;
+        let mut file: Option<String> = None;
+        let mut range: Option<[usize; 2]> = None;
+        loop {
+            let key = parse_string(bytes, &mut i).ok_or_else(err)?;
+            expect(&mut i, b':')?;
+            match key.as_str() {
+                "file" => file = Some(parse_string(bytes, &mut i).ok_or_else(err)?),
+                "range" => {
+                    expect(&mut i, b'[')?;
+                    let start = parse_number(bytes, &mut i).ok_or_else(err)?;
+                    expect(&mut i, b',')?;
+                    let end = parse_number(bytes, &mut i).ok_or_else(err)?;
+                    expect(&mut i, b']')?;
+                    range = Some([start, end]);
+                }
+                _ => return Err(err()),
+            }
+            skip_ws(bytes, &mut i);
+            match bytes.get(i) {
+                Some(&b',') => i += 1,
+                Some(&b'}') => {
+                    i += 1;
+                    break;
+                }
+                _ => return Err(err()),
+            }
+        }
+        let file = file.ok_or_else(err)?;
+        let range = range.ok_or_else(err)?;
+        specs.push((
+            file,
+            LineRange {
+                start: range[0],
+                end: range[1],
+            },
+        ));
+        skip_ws(bytes, &mut i);
+        match bytes.get(i) {
+            Some(&b',') => i += 1,
+            Some(&b']') => break,
+            _ => return Err(err()),
+        }
+    }
+    Ok(specs)
+}
+
+fn skip_ws(bytes: &[u8], i: &mut usize) {
+    while matches!(bytes.get(*i), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+        *i += 1;
+    }
+}
+
+fn parse_string(bytes: &[u8], i: &mut usize) -> Option<String> {
+    skip_ws(bytes, i);
+    if bytes.get(*i) != Some(&b'"') {
+        return None;
+    }
+    *i += 1;
+    let mut out = String::new();
+    while let Some(&c) = bytes.get(*i) {
+        *i += 1;
+        match c {
+            b'"' => return Some(out),
+            b'\\' => {
+                let esc = *bytes.get(*i)?;
+                *i += 1;
+                match esc {
+                    b'"' => out.push('"'),
+                    b'\\' => out.push('\\'),
+                    b'/' => out.push('/'),
+                    b'n' => out.push('\n'),
+                    b't' => out.push('\t'),
+                    b'r' => out.push('\r'),
+                    _ => return None,
+                }
+            }
+            _ => out.push(c as char),
+        }
+    }
+    None
+}
+
+fn parse_number(bytes: &[u8], i: &mut usize) -> Option<usize> {
+    skip_ws(bytes, i);
+    let start = *i;
+    while matches!(bytes.get(*i), Some(b'0'..=b'9')) {
+        *i += 1;
+    }
+    if *i == start {
+        return None;
+    }
+    std::str::from_utf8(&bytes[start..*i]).ok()?.parse().ok()
+}
+
+fn parse_shorthand(arg: &str) -> Result<(String, LineRange), String> {
+    let (file, span) = arg
+        .rsplit_once(':')
+        .ok_or_else(|| format!("invalid --file-lines spec `{arg}`, expected `file:start-end`"))?;
+    let (start, end) = span
+        .split_once('-')
+        .ok_or_else(|| format!("invalid --file-lines range `{span}`, expected `start-end`"))?;
+    let start = start
+        .parse()
+        .map_err(|_| format!("invalid --file-lines start `{start}`"))?;
+    let end = end
+        .parse()
+        .map_err(|_| format!("invalid --file-lines end `{end}`"))?;
+    Ok((file.to_string(), LineRange { start, end }))
+}
+
+/// A contiguous run of changed lines, with half-open `[start, end)` indices
+/// into the original and formatted line vectors.
+struct Hunk {
+    old_start: usize,
+    old_end: usize,
+    new_start: usize,
+    new_end: usize,
+}
+
+/// Aligns `old` and `new` line-by-line with an LCS walk and returns the
+/// changed runs. Unchanged lines between hunks are implied by the gaps.
+fn hunks(old: &[&str], new: &[&str]) -> Vec<Hunk> {
+    let n = old.len();
+    let m = new.len();
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let (os, ns) = (i, j);
+        while i < n && j < m && old[i] != new[j] {
+            if lcs[i + 1][j] >= lcs[i][j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        hunks.push(Hunk {
+            old_start: os,
+            old_end: i,
+            new_start: ns,
+            new_end: j,
+        });
+    }
+    if i < n || j < m {
+        hunks.push(Hunk {
+            old_start: i,
+            old_end: n,
+            new_start: j,
+            new_end: m,
+        });
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_json_form() {
+        let specs = parse_json("[{\"file\":\"a.hurl\",\"range\":[12,40]}]").unwrap();
+        assert_eq!(specs, vec![("a.hurl".to_string(), LineRange { start: 12, end: 40 })]);
+    }
+
+    #[test]
+    fn parses_empty_json_array() {
+        assert_eq!(parse_json("[]").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn rejects_malformed_json() {
+        assert!(parse_json("[{\"file\":\"a.hurl\"").is_err());
+        assert!(parse_json("[{\"range\":[1,2]}]").is_err());
+    }
+
+    #[test]
+    fn parses_shorthand_form() {
+        let lines = FileLines::parse("a.hurl:3-7").unwrap();
+        assert_eq!(
+            lines.ranges_for("a.hurl"),
+            Some([LineRange { start: 3, end: 7 }].as_slice())
+        );
+    }
+
+    #[test]
+    fn splice_preserves_trailing_newline() {
+        let lines = FileLines::parse("a.hurl:1-2").unwrap();
+        let src = "one\ntwo\n";
+        assert_eq!(lines.splice("a.hurl", src, src), src);
+    }
+
+    #[test]
+    fn splice_preserves_crlf_line_endings() {
+        let lines = FileLines::parse("a.hurl:1-2").unwrap();
+        let src = "one\r\ntwo\r\n";
+        assert_eq!(lines.splice("a.hurl", src, src), src);
+    }
+
+    #[test]
+    fn hunks_report_pure_insertion() {
+        let hunks = hunks(&["a", "b"], &["a", "x", "b"]);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].old_start, hunks[0].old_end);
+        assert_eq!(hunks[0].new_end - hunks[0].new_start, 1);
+    }
+
+    #[test]
+    fn hunks_report_pure_deletion() {
+        let hunks = hunks(&["a", "x", "b"], &["a", "b"]);
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].new_start, hunks[0].new_end);
+        assert_eq!(hunks[0].old_end - hunks[0].old_start, 1);
+    }
+}
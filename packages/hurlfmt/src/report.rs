@@ -0,0 +1,178 @@
+/*
+ * Hurl (https://hurl.dev)
+ * Copyright (C) 2024 Orange
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *          http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ *
+ */
+//! Machine-readable lint reports, modeled on rustfmt's `checkstyle` write mode.
+//!
+//! A run aggregates one [`LintEntry`] per finding across every input file and
+//! emits a single consolidated document at the end in the format requested by
+//! `--report-format`.
+
+use std::collections::BTreeMap;
+
+/// Output format for the aggregated lint report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Human-colored text (the historical behavior).
+    Text,
+    /// Checkstyle XML.
+    Checkstyle,
+    /// JSON array of findings.
+    Json,
+}
+
+/// A single lint finding, ready to be serialized into any report format.
+#[derive(Clone, Debug)]
+pub struct LintEntry {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub severity: String,
+    pub rule: String,
+    pub message: String,
+}
+
+/// Renders `entries` as a Checkstyle XML document, grouping findings by file.
+pub fn format_checkstyle(entries: &[LintEntry]) -> String {
+    let mut by_file: BTreeMap<&str, Vec<&LintEntry>> = BTreeMap::new();
+    for entry in entries {
+        by_file.entry(&entry.file).or_default().push(entry);
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    out.push_str("<checkstyle version=\"4.3\">\n");
+    for (file, findings) in by_file {
+        out.push_str(&format!("  <file name=\"{}\">\n", xml_escape(file)));
+        for f in findings {
+            out.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"{}\" message=\"{}\" source=\"{}\" />\n",
+                f.line,
+                f.column,
+                xml_escape(&f.severity),
+                xml_escape(&f.message),
+                xml_escape(&f.rule),
+            ));
+        }
+        out.push_str("  </file>\n");
+    }
+    out.push_str("</checkstyle>\n");
+    out
+}
+
+/// Renders `entries` as a JSON array of finding objects.
+pub fn format_json(entries: &[LintEntry]) -> String {
+    let mut out = String::from("[");
+    for (i, e) in entries.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&format!(
+            "{{\"file\":{},\"line\":{},\"column\":{},\"severity\":{},\"rule\":{},\"message\":{}}}",
+            json_string(&e.file),
+            e.line,
+            e.column,
+            json_string(&e.severity),
+            json_string(&e.rule),
+            json_string(&e.message),
+        ));
+    }
+    out.push(']');
+    out
+}
+
+/// Escapes the five XML predefined entities for safe attribute values.
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Serializes `s` as a quoted JSON string with the mandatory escapes.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> LintEntry {
+        LintEntry {
+            file: "a.hurl".to_string(),
+            line: 3,
+            column: 5,
+            severity: "warning".to_string(),
+            rule: "one-space".to_string(),
+            message: "One space <expected>".to_string(),
+        }
+    }
+
+    #[test]
+    fn escapes_xml_entities() {
+        assert_eq!(xml_escape("a<b>&\"'"), "a&lt;b&gt;&amp;&quot;&apos;");
+    }
+
+    #[test]
+    fn escapes_json_control_characters() {
+        assert_eq!(json_string("a\"b\\c\n\t"), "\"a\\\"b\\\\c\\n\\t\"");
+    }
+
+    #[test]
+    fn checkstyle_escapes_message() {
+        let xml = format_checkstyle(&[entry()]);
+        assert!(xml.contains("<file name=\"a.hurl\">"));
+        assert!(xml.contains("message=\"One space &lt;expected&gt;\""));
+        assert!(xml.contains("source=\"one-space\""));
+    }
+
+    #[test]
+    fn json_serializes_fields() {
+        let json = format_json(&[entry()]);
+        assert_eq!(
+            json,
+            "[{\"file\":\"a.hurl\",\"line\":3,\"column\":5,\"severity\":\"warning\",\"rule\":\"one-space\",\"message\":\"One space <expected>\"}]"
+        );
+    }
+
+    #[test]
+    fn json_of_no_entries_is_empty_array() {
+        assert_eq!(format_json(&[]), "[]");
+    }
+}